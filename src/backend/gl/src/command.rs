@@ -2,12 +2,13 @@
 
 use gl;
 
-use hal::format::ChannelType;
+use hal::format::{self, ChannelType};
 use hal::range::RangeArg;
 use hal::{self, buffer, command, image, memory, pass, pso, query, ColorSlot};
 
+use info::PrivateCaps;
 use pool::{self, BufferMemory};
-use {native as n, Backend};
+use {conv, native as n, Backend};
 
 use std::borrow::Borrow;
 use std::ops::Range;
@@ -76,6 +77,14 @@ pub enum Command {
     },
     SetScissors(u32, BufferSlice),
     SetBlendColor(pso::ColorValue),
+    /// Set the stencil test with the (front, back) reference values,
+    /// read masks and write masks used for dynamic states.
+    SetStencilState(
+        pso::StencilTest,
+        (pso::StencilValue, pso::StencilValue),
+        (pso::StencilValue, pso::StencilValue),
+        (pso::StencilValue, pso::StencilValue),
+    ),
 
     /// Clear floating-point color drawbuffer of bound framebuffer.
     ClearBufferColorF(DrawBuffer, [f32; 4]),
@@ -91,6 +100,9 @@ pub enum Command {
     /// Set list of color attachments for drawing.
     /// The buffer slice contains a list of `GLenum`.
     DrawBuffers(BufferSlice),
+    /// Invalidate the contents of framebuffer attachments.
+    /// The buffer slice contains a list of `GLenum`.
+    InvalidateFramebuffer(FrameBufferTarget, BufferSlice),
 
     BindFrameBuffer(FrameBufferTarget, n::FrameBuffer),
     BindTargetView(FrameBufferTarget, AttachmentPoint, n::ImageView),
@@ -112,6 +124,14 @@ pub enum Command {
     CopySurfaceToBuffer(n::Surface, n::RawBuffer, command::BufferImageCopy),
     CopyImageToTexture(n::ImageKind, n::Texture, command::ImageCopy),
     CopyImageToSurface(n::ImageKind, n::Surface, command::ImageCopy),
+    /// Blit between the bound read and draw framebuffers.
+    /// Rectangles are given as `[x0, y0, x1, y1]`.
+    BlitFramebuffer {
+        src: [gl::types::GLint; 4],
+        dst: [gl::types::GLint; 4],
+        mask: gl::types::GLbitfield,
+        filter: gl::types::GLenum,
+    },
 
     BindBufferRange(
         gl::types::GLenum,
@@ -123,6 +143,8 @@ pub enum Command {
     BindTexture(gl::types::GLenum, n::Texture),
     BindSampler(gl::types::GLuint, n::Texture),
     SetTextureSamplerSettings(gl::types::GLuint, n::Texture, image::SamplerInfo),
+    /// Order incoherent memory accesses, takes the `glMemoryBarrier` bits.
+    MemoryBarrier(gl::types::GLbitfield),
 }
 
 pub type FrameBufferTarget = gl::types::GLenum;
@@ -151,6 +173,12 @@ struct Cache {
     index_type: Option<hal::IndexType>,
     // Stencil reference values (front, back).
     stencil_ref: Option<(pso::StencilValue, pso::StencilValue)>,
+    // Stencil read masks (front, back).
+    stencil_read_mask: Option<(pso::StencilValue, pso::StencilValue)>,
+    // Stencil write masks (front, back).
+    stencil_write_mask: Option<(pso::StencilValue, pso::StencilValue)>,
+    // Stencil test, set by the current pipeline.
+    stencil: Option<pso::StencilTest>,
     // Blend color.
     blend_color: Option<pso::ColorValue>,
     ///
@@ -178,6 +206,9 @@ impl Cache {
             primitive: None,
             index_type: None,
             stencil_ref: None,
+            stencil_read_mask: None,
+            stencil_write_mask: None,
+            stencil: None,
             blend_color: None,
             framebuffer: None,
             error_state: false,
@@ -206,6 +237,15 @@ impl From<hal::Limits> for Limits {
     }
 }
 
+// Attachable view of a single layer of an image.
+fn layer_view(image: &n::Image, level: image::Level, layer: image::Layer) -> n::ImageView {
+    match image.kind {
+        n::ImageKind::Surface(surface) => n::ImageView::Surface(surface),
+        n::ImageKind::Texture(texture) if layer == 0 => n::ImageView::Texture(texture, level),
+        n::ImageKind::Texture(texture) => n::ImageView::TextureLayer(texture, level, layer),
+    }
+}
+
 /// A command buffer abstraction for OpenGL.
 ///
 /// If you want to display your rendered results to a framebuffer created externally, see the
@@ -219,6 +259,8 @@ pub struct RawCommandBuffer {
     individual_reset: bool,
 
     fbo: Option<n::FrameBuffer>,
+    // Read framebuffer for blits, `fbo` is used as the draw framebuffer.
+    blit_fbo: Option<n::FrameBuffer>,
     /// The framebuffer to use for rendering to the main targets (0 by default).
     ///
     /// Use this to set the framebuffer that will be used for the screen display targets created
@@ -235,13 +277,16 @@ pub struct RawCommandBuffer {
     cur_subpass: usize,
 
     limits: Limits,
+    private_caps: PrivateCaps,
     active_attribs: usize,
 }
 
 impl RawCommandBuffer {
     pub(crate) fn new(
         fbo: Option<n::FrameBuffer>,
+        blit_fbo: Option<n::FrameBuffer>,
         limits: Limits,
+        private_caps: PrivateCaps,
         memory: Arc<Mutex<BufferMemory>>,
     ) -> Self {
         let (id, individual_reset) = {
@@ -270,11 +315,13 @@ impl RawCommandBuffer {
             id,
             individual_reset,
             fbo,
+            blit_fbo,
             display_fb: 0 as n::FrameBuffer,
             cache: Cache::new(),
             pass_cache: None,
             cur_subpass: !0,
             limits,
+            private_caps,
             active_attribs: 0,
         }
     }
@@ -320,6 +367,83 @@ impl RawCommandBuffer {
         slice
     }
 
+    // Returns the (read, draw) framebuffers used for blits.
+    fn blit_framebuffers(&mut self) -> Option<(n::FrameBuffer, n::FrameBuffer)> {
+        match (self.blit_fbo, self.fbo) {
+            (Some(read), Some(draw)) => Some((read, draw)),
+            _ => {
+                error!("Blitting images requires framebuffer objects");
+                self.cache.error_state = true;
+                None
+            }
+        }
+    }
+
+    // Blit between two single layer views, rects are given as `[x0, y0, x1, y1]`.
+    fn push_blit(
+        &mut self,
+        (read_fbo, draw_fbo): (n::FrameBuffer, n::FrameBuffer),
+        src: n::ImageView,
+        dst: n::ImageView,
+        aspects: format::Aspects,
+        (src_rect, dst_rect): ([gl::types::GLint; 4], [gl::types::GLint; 4]),
+        filter: image::Filter,
+    ) {
+        let (attachment, mask) = if aspects.contains(format::Aspects::COLOR) {
+            (gl::COLOR_ATTACHMENT0, gl::COLOR_BUFFER_BIT)
+        } else if aspects.contains(format::Aspects::DEPTH | format::Aspects::STENCIL) {
+            (
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT,
+            )
+        } else if aspects.contains(format::Aspects::DEPTH) {
+            (gl::DEPTH_ATTACHMENT, gl::DEPTH_BUFFER_BIT)
+        } else {
+            (gl::STENCIL_ATTACHMENT, gl::STENCIL_BUFFER_BIT)
+        };
+        // Depth and stencil can only be blitted with nearest filtering.
+        let filter = match filter {
+            image::Filter::Linear if mask == gl::COLOR_BUFFER_BIT => gl::LINEAR,
+            _ => gl::NEAREST,
+        };
+
+        self.push_cmd(Command::BindFrameBuffer(gl::READ_FRAMEBUFFER, read_fbo));
+        self.push_cmd(Command::BindTargetView(
+            gl::READ_FRAMEBUFFER,
+            attachment,
+            src,
+        ));
+        self.push_cmd(Command::BindFrameBuffer(gl::DRAW_FRAMEBUFFER, draw_fbo));
+        self.push_cmd(Command::BindTargetView(
+            gl::DRAW_FRAMEBUFFER,
+            attachment,
+            dst,
+        ));
+        if mask == gl::COLOR_BUFFER_BIT {
+            self.push_cmd(Command::SetDrawColorBuffers(1));
+        }
+        self.push_cmd(Command::BlitFramebuffer {
+            src: src_rect,
+            dst: dst_rect,
+            mask,
+            filter,
+        });
+    }
+
+    fn update_stencil_state(&mut self) {
+        if let Some(stencil) = self.cache.stencil {
+            let refs = self.cache.stencil_ref.unwrap_or((0, 0));
+            let read_masks = self.cache.stencil_read_mask.unwrap_or((!0, !0));
+            let write_masks = self.cache.stencil_write_mask.unwrap_or((!0, !0));
+            self.push_cmd(Command::SetStencilState(
+                stencil,
+                refs,
+                read_masks,
+                write_masks,
+            ));
+        }
+    }
+
     fn update_blend_targets(&mut self, blend_targets: &Vec<pso::ColorBlendDesc>) {
         let max_blend_slots = blend_targets.len();
 
@@ -550,12 +674,29 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
         &mut self,
         _stages: Range<hal::pso::PipelineStage>,
         _dependencies: memory::Dependencies,
-        _barriers: T,
+        barriers: T,
     ) where
         T: IntoIterator,
         T::Item: Borrow<memory::Barrier<'a, Backend>>,
     {
-        // TODO
+        // Storage buffers are the only incoherently written resources
+        // this backend binds so far, everything else is ordered by GL.
+        // The barrier bits describe how the written data is accessed afterwards.
+        let mut bits = 0;
+        for barrier in barriers {
+            let access = match *barrier.borrow() {
+                memory::Barrier::AllBuffers(ref access) => access,
+                memory::Barrier::Buffer { ref states, .. } => states,
+                memory::Barrier::AllImages(..) | memory::Barrier::Image { .. } => continue,
+            };
+            if access.start.contains(buffer::Access::SHADER_WRITE) {
+                bits |= conv::buffer_access_to_gl_barrier(access.end);
+            }
+        }
+
+        if bits != 0 {
+            self.push_cmd(Command::MemoryBarrier(bits));
+        }
     }
 
     unsafe fn fill_buffer<R>(&mut self, _buffer: &n::Buffer, _range: R, _data: u32)
@@ -590,9 +731,7 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
         //      select correct ClearBuffer variant.
         //    * Check for attachment loading clearing strategy
 
-        // TODO: store ops:
-        //   < GL 4.5: Ignore
-        //  >= GL 4.5: Invalidate framebuffer attachment when store op is `DONT_CARE`.
+        // Store ops are handled in `end_render_pass`.
 
         // 2./3.
         self.push_cmd(Command::BindFrameBuffer(gl::DRAW_FRAMEBUFFER, *framebuffer));
@@ -640,7 +779,57 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
     }
 
     unsafe fn end_render_pass(&mut self) {
-        // TODO
+        // Invalidate attachments which don't need to be stored,
+        // so tiled implementations can skip writing them back.
+        let attachments = {
+            let state = match self.pass_cache {
+                Some(ref state) => state,
+                None => return,
+            };
+            let is_default = state.framebuffer == n::DEFAULT_FRAMEBUFFER;
+            let dont_care = pass::AttachmentStoreOp::DontCare;
+
+            let mut attachments = Vec::new();
+            for (i, attachment) in state.render_pass.attachments.iter().enumerate() {
+                let format = match attachment.format {
+                    Some(format) => format,
+                    None => continue,
+                };
+                if format.is_color() {
+                    if attachment.ops.store == dont_care {
+                        attachments.push(if is_default {
+                            gl::COLOR
+                        } else {
+                            gl::COLOR_ATTACHMENT0 + i as gl::types::GLenum
+                        });
+                    }
+                } else {
+                    if format.is_depth() && attachment.ops.store == dont_care {
+                        attachments.push(if is_default {
+                            gl::DEPTH
+                        } else {
+                            gl::DEPTH_ATTACHMENT
+                        });
+                    }
+                    if format.is_stencil() && attachment.stencil_ops.store == dont_care {
+                        attachments.push(if is_default {
+                            gl::STENCIL
+                        } else {
+                            gl::STENCIL_ATTACHMENT
+                        });
+                    }
+                }
+            }
+            attachments
+        };
+
+        if !attachments.is_empty() {
+            let attachments = self.add(&attachments);
+            self.push_cmd(Command::InvalidateFramebuffer(
+                gl::DRAW_FRAMEBUFFER,
+                attachments,
+            ));
+        }
     }
 
     unsafe fn clear_image<T>(
@@ -728,17 +917,52 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
 
     unsafe fn blit_image<T>(
         &mut self,
-        _src: &n::Image,
+        src: &n::Image,
         _src_layout: image::Layout,
-        _dst: &n::Image,
+        dst: &n::Image,
         _dst_layout: image::Layout,
-        _filter: image::Filter,
-        _regions: T,
+        filter: image::Filter,
+        regions: T,
     ) where
         T: IntoIterator,
         T::Item: Borrow<command::ImageBlit>,
     {
-        unimplemented!()
+        let fbos = match self.blit_framebuffers() {
+            Some(fbos) => fbos,
+            None => return,
+        };
+
+        for region in regions {
+            let r = region.borrow();
+            let src_rect = [
+                r.src_bounds.start.x,
+                r.src_bounds.start.y,
+                r.src_bounds.end.x,
+                r.src_bounds.end.y,
+            ];
+            let dst_rect = [
+                r.dst_bounds.start.x,
+                r.dst_bounds.start.y,
+                r.dst_bounds.end.x,
+                r.dst_bounds.end.y,
+            ];
+
+            let layers = r
+                .src_subresource
+                .layers
+                .clone()
+                .zip(r.dst_subresource.layers.clone());
+            for (src_layer, dst_layer) in layers {
+                self.push_blit(
+                    fbos,
+                    layer_view(src, r.src_subresource.level, src_layer),
+                    layer_view(dst, r.dst_subresource.level, dst_layer),
+                    r.src_subresource.aspects,
+                    (src_rect, dst_rect),
+                    filter,
+                );
+            }
+        }
     }
 
     unsafe fn bind_index_buffer(&mut self, ibv: buffer::IndexBufferView<Backend>) {
@@ -868,18 +1092,46 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
             back = value;
         }
 
-        // Only cache the stencil references values until
-        // we assembled all the pieces to set the stencil state
-        // from the pipeline.
+        // The stencil state is set together with the pipeline's stencil test,
+        // so this only takes effect once a pipeline is bound.
         self.cache.stencil_ref = Some((front, back));
+        self.update_stencil_state();
     }
 
-    unsafe fn set_stencil_read_mask(&mut self, _faces: pso::Face, _value: pso::StencilValue) {
-        unimplemented!();
+    unsafe fn set_stencil_read_mask(&mut self, faces: pso::Face, value: pso::StencilValue) {
+        assert!(!faces.is_empty());
+
+        let (mut front, mut back) = self.cache.stencil_read_mask.unwrap_or((!0, !0));
+
+        if faces.contains(pso::Face::FRONT) {
+            front = value;
+        }
+
+        if faces.contains(pso::Face::BACK) {
+            back = value;
+        }
+
+        // Set together with the stencil references, see `set_stencil_reference`.
+        self.cache.stencil_read_mask = Some((front, back));
+        self.update_stencil_state();
     }
 
-    unsafe fn set_stencil_write_mask(&mut self, _faces: pso::Face, _value: pso::StencilValue) {
-        unimplemented!();
+    unsafe fn set_stencil_write_mask(&mut self, faces: pso::Face, value: pso::StencilValue) {
+        assert!(!faces.is_empty());
+
+        let (mut front, mut back) = self.cache.stencil_write_mask.unwrap_or((!0, !0));
+
+        if faces.contains(pso::Face::FRONT) {
+            front = value;
+        }
+
+        if faces.contains(pso::Face::BACK) {
+            back = value;
+        }
+
+        // Set together with the stencil references, see `set_stencil_reference`.
+        self.cache.stencil_write_mask = Some((front, back));
+        self.update_stencil_state();
     }
 
     unsafe fn set_blend_constants(&mut self, cv: pso::ColorValue) {
@@ -907,6 +1159,7 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
             patch_size,
             program,
             ref blend_targets,
+            stencil,
             ref attributes,
             ref vertex_buffers,
         } = *pipeline;
@@ -932,6 +1185,11 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
         self.cache.vertex_buffer_descs = vertex_buffers.clone();
 
         self.update_blend_targets(blend_targets);
+
+        if self.cache.stencil != Some(stencil) {
+            self.cache.stencil = Some(stencil);
+            self.update_stencil_state();
+        }
     }
 
     unsafe fn bind_graphics_descriptor_sets<I, J>(
@@ -963,16 +1221,14 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
                         offset,
                         size,
                     } => {
-                        let btype = match btype {
+                        let target = match btype {
                             n::BindingTypes::UniformBuffers => gl::UNIFORM_BUFFER,
+                            n::BindingTypes::StorageBuffers => gl::SHADER_STORAGE_BUFFER,
                             n::BindingTypes::Images => panic!("Wrong desc set binding"),
                         };
-                        for binding in drd
-                            .get_binding(n::BindingTypes::UniformBuffers, set, *binding)
-                            .unwrap()
-                        {
+                        for binding in drd.get_binding(*btype, set, *binding).unwrap() {
                             self.push_cmd(Command::BindBufferRange(
-                                btype, *binding, *buffer, *offset, *size,
+                                target, *binding, *buffer, *offset, *size,
                             ))
                         }
                     }
@@ -1099,13 +1355,47 @@ impl command::RawCommandBuffer<Backend> for RawCommandBuffer {
     {
         let old_offset = self.buf.offset;
 
-        for region in regions {
-            let r = region.borrow().clone();
-            let cmd = match dst.kind {
-                n::ImageKind::Surface(s) => Command::CopyImageToSurface(src.kind, s, r),
-                n::ImageKind::Texture(t) => Command::CopyImageToTexture(src.kind, t, r),
+        if !self.private_caps.copy_image {
+            // Fall back to same-size blits without `glCopyImageSubData`.
+            let fbos = match self.blit_framebuffers() {
+                Some(fbos) => fbos,
+                None => return,
             };
-            self.push_cmd(cmd);
+            for region in regions {
+                let r = region.borrow();
+                let rect = |offset: image::Offset| {
+                    [
+                        offset.x,
+                        offset.y,
+                        offset.x + r.extent.width as i32,
+                        offset.y + r.extent.height as i32,
+                    ]
+                };
+                // Array layers and the depth slices of 3D images are both
+                // attached as framebuffer layers.
+                let layers = r.src_subresource.layers.end - r.src_subresource.layers.start;
+                for i in 0..layers as i32 * r.extent.depth as i32 {
+                    let src_layer = r.src_subresource.layers.start as i32 + r.src_offset.z + i;
+                    let dst_layer = r.dst_subresource.layers.start as i32 + r.dst_offset.z + i;
+                    self.push_blit(
+                        fbos,
+                        layer_view(src, r.src_subresource.level, src_layer as _),
+                        layer_view(dst, r.dst_subresource.level, dst_layer as _),
+                        r.src_subresource.aspects,
+                        (rect(r.src_offset), rect(r.dst_offset)),
+                        image::Filter::Nearest,
+                    );
+                }
+            }
+        } else {
+            for region in regions {
+                let r = region.borrow().clone();
+                let cmd = match dst.kind {
+                    n::ImageKind::Surface(s) => Command::CopyImageToSurface(src.kind, s, r),
+                    n::ImageKind::Texture(t) => Command::CopyImageToTexture(src.kind, t, r),
+                };
+                self.push_cmd(cmd);
+            }
         }
 
         if self.buf.offset == old_offset {
@@ -56,6 +56,37 @@ pub fn buffer_usage_to_gl_target(usage: buffer::Usage) -> Option<t::GLenum> {
     }
 }
 
+/// Map the accesses following a barrier to the matching `glMemoryBarrier` bits.
+pub fn buffer_access_to_gl_barrier(access: buffer::Access) -> t::GLbitfield {
+    use self::buffer::Access;
+    if access.intersects(Access::MEMORY_READ | Access::MEMORY_WRITE) {
+        return gl::ALL_BARRIER_BITS;
+    }
+    let mut bits = 0;
+    if access.intersects(Access::TRANSFER_READ | Access::TRANSFER_WRITE) {
+        bits |= gl::BUFFER_UPDATE_BARRIER_BIT;
+    }
+    if access.contains(Access::HOST_READ) {
+        bits |= gl::BUFFER_UPDATE_BARRIER_BIT | gl::CLIENT_MAPPED_BUFFER_BARRIER_BIT;
+    }
+    if access.contains(Access::VERTEX_BUFFER_READ) {
+        bits |= gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT;
+    }
+    if access.contains(Access::INDEX_BUFFER_READ) {
+        bits |= gl::ELEMENT_ARRAY_BARRIER_BIT;
+    }
+    if access.contains(Access::INDIRECT_COMMAND_READ) {
+        bits |= gl::COMMAND_BARRIER_BIT;
+    }
+    if access.contains(Access::CONSTANT_BUFFER_READ) {
+        bits |= gl::UNIFORM_BARRIER_BIT;
+    }
+    if access.intersects(Access::SHADER_READ | Access::SHADER_WRITE) {
+        bits |= gl::SHADER_STORAGE_BARRIER_BIT;
+    }
+    bits
+}
+
 pub fn primitive_to_gl_primitive(primitive: Primitive) -> t::GLenum {
     match primitive {
         Primitive::PointList => gl::POINTS,
@@ -17,7 +17,7 @@ use hal::{self as c, buffer, device as d, error, image as i, mapping, memory, pa
 
 use spirv_cross::{glsl, spirv, ErrorCode as SpirvErrorCode};
 
-use info::LegacyFeatures;
+use info::{self, LegacyFeatures};
 use pool::{BufferMemory, OwnedBuffer, RawCommandPool};
 use {conv, native as n, state};
 use {Backend as B, Share, Starc, Surface, Swapchain};
@@ -312,6 +312,13 @@ impl Device {
             &res.uniform_buffers,
             n::BindingTypes::UniformBuffers,
         );
+        self.remap_binding(
+            ast,
+            desc_remap_data,
+            nb_map,
+            &res.storage_buffers,
+            n::BindingTypes::StorageBuffers,
+        );
     }
 
     fn remap_binding(
@@ -536,6 +543,7 @@ impl d::Device<B> for Device {
         flags: CommandPoolCreateFlags,
     ) -> Result<RawCommandPool, d::OutOfMemory> {
         let fbo = create_fbo_internal(&self.share);
+        let blit_fbo = create_fbo_internal(&self.share);
         let limits = self.share.limits.into();
         let memory = if flags.contains(CommandPoolCreateFlags::RESET_INDIVIDUAL) {
             BufferMemory::Individual {
@@ -550,14 +558,19 @@ impl d::Device<B> for Device {
 
         Ok(RawCommandPool {
             fbo,
+            blit_fbo,
             limits,
+            private_caps: self.share.private_caps,
             memory: Arc::new(Mutex::new(memory)),
         })
     }
 
     unsafe fn destroy_command_pool(&self, pool: RawCommandPool) {
+        let gl = &self.share.context;
         if let Some(fbo) = pool.fbo {
-            let gl = &self.share.context;
+            gl.DeleteFramebuffers(1, &fbo);
+        }
+        if let Some(fbo) = pool.blit_fbo {
             gl.DeleteFramebuffers(1, &fbo);
         }
     }
@@ -612,8 +625,8 @@ impl d::Device<B> for Device {
     {
         let mut drd = n::DescRemapData::new();
 
-        layouts.into_iter().enumerate().for_each(|(set, layout)| {
-            layout.borrow().iter().for_each(|binding| {
+        for (set, layout) in layouts.into_iter().enumerate() {
+            for binding in layout.borrow().iter() {
                 // DescriptorType -> Descriptor
                 //
                 // Sampler -> Sampler
@@ -644,12 +657,24 @@ impl d::Device<B> for Device {
                             binding.binding,
                         );
                     }
+                    StorageBuffer => {
+                        if !self.share.private_caps.storage_buffer {
+                            error!("Storage buffers are not supported");
+                            return Err(d::OutOfMemory::OutOfHostMemory);
+                        }
+                        drd.insert_missing_binding_into_spare(
+                            n::BindingTypes::StorageBuffers,
+                            set as _,
+                            binding.binding,
+                        );
+                    }
                     StorageImage | UniformTexelBuffer | UniformBufferDynamic
-                    | StorageTexelBuffer | StorageBufferDynamic | StorageBuffer
-                    | InputAttachment => unimplemented!(), // 6
+                    | StorageTexelBuffer | StorageBufferDynamic | InputAttachment => {
+                        unimplemented!()
+                    } // 5
                 }
-            })
-        });
+            }
+        }
 
         Ok(n::PipelineLayout {
             desc_remap_data: Arc::new(RwLock::new(drd)),
@@ -694,6 +719,12 @@ impl d::Device<B> for Device {
             }
         };
 
+        let dual_source = desc.blender.targets.iter().any(|t| t.1.is_dual_source());
+        if dual_source && !share.features.contains(hal::Features::DUAL_SRC_BLENDING) {
+            error!("Dual-source blending is not supported");
+            return Err(pso::CreationError::Other);
+        }
+
         let program = {
             let name = gl.CreateProgram();
 
@@ -793,6 +824,7 @@ impl d::Device<B> for Device {
             primitive: conv::primitive_to_gl_primitive(desc.input_assembler.primitive),
             patch_size,
             blend_targets: desc.blender.targets.clone(),
+            stencil: desc.depth_stencil.stencil,
             vertex_buffers,
             attributes: desc
                 .attributes
@@ -875,7 +907,7 @@ impl d::Device<B> for Device {
         &self,
         pass: &n::RenderPass,
         attachments: I,
-        _extent: i::Extent,
+        extent: i::Extent,
     ) -> Result<n::FrameBuffer, d::OutOfMemory>
     where
         I: IntoIterator,
@@ -886,6 +918,32 @@ impl d::Device<B> for Device {
         }
 
         let gl = &self.share.context;
+
+        // The framebuffer size is otherwise undefined without attachments.
+        if pass.attachments.is_empty() {
+            if !self.share.private_caps.framebuffer_no_attachments {
+                error!("Framebuffers without attachments are not supported");
+                return Err(d::OutOfMemory::OutOfHostMemory);
+            }
+            if extent.width == 0 || extent.height == 0 {
+                error!("Framebuffers without attachments require a non-zero extent");
+                return Err(d::OutOfMemory::OutOfHostMemory);
+            }
+            let max_width = info::get_usize(gl, gl::MAX_FRAMEBUFFER_WIDTH).unwrap_or(0);
+            let max_height = info::get_usize(gl, gl::MAX_FRAMEBUFFER_HEIGHT).unwrap_or(0);
+            if extent.width as usize > max_width || extent.height as usize > max_height {
+                error!(
+                    "Framebuffer extent {:?} exceeds the maximum of {}x{}",
+                    extent, max_width, max_height
+                );
+                return Err(d::OutOfMemory::OutOfHostMemory);
+            }
+            if extent.depth > 1 && !self.share.private_caps.framebuffer_no_attachments_layers {
+                error!("Layered framebuffers without attachments are not supported");
+                return Err(d::OutOfMemory::OutOfHostMemory);
+            }
+        }
+
         let target = gl::DRAW_FRAMEBUFFER;
         let mut name = 0;
         gl.GenFramebuffers(1, &mut name);
@@ -912,6 +970,13 @@ impl d::Device<B> for Device {
         // attachments_len actually equals min(attachments.len(), att_points.len()) until the next assert
 
         assert!(pass.attachments.len() <= att_points.len());
+        if attachments_len == 0 {
+            gl.FramebufferParameteri(target, gl::FRAMEBUFFER_DEFAULT_WIDTH, extent.width as _);
+            gl.FramebufferParameteri(target, gl::FRAMEBUFFER_DEFAULT_HEIGHT, extent.height as _);
+            if extent.depth > 1 {
+                gl.FramebufferParameteri(target, gl::FRAMEBUFFER_DEFAULT_LAYERS, extent.depth as _);
+            }
+        }
         gl.DrawBuffers(attachments_len as _, att_points.as_ptr());
         let _status = gl.CheckFramebufferStatus(target); //TODO: check status
         gl.BindFramebuffer(target, 0);
@@ -1342,7 +1407,13 @@ impl d::Device<B> for Device {
             let set = &mut write.set;
             let mut bindings = set.bindings.lock().unwrap();
             let binding = write.binding;
-            let mut offset = write.array_offset as _;
+            let buffer_ty = match set.layout.iter().find(|b| b.binding == binding) {
+                Some(&pso::DescriptorSetLayoutBinding {
+                    ty: pso::DescriptorType::StorageBuffer,
+                    ..
+                }) => n::BindingTypes::StorageBuffers,
+                _ => n::BindingTypes::UniformBuffers,
+            };
 
             for descriptor in write.descriptors {
                 match descriptor.borrow() {
@@ -1350,16 +1421,27 @@ impl d::Device<B> for Device {
                         let start = range.start.unwrap_or(0);
                         let end = range.end.unwrap_or(buffer.requirements.size);
                         let size = (end - start) as _;
+                        let alignment = match buffer_ty {
+                            n::BindingTypes::StorageBuffers => {
+                                self.share.limits.min_storage_buffer_offset_alignment
+                            }
+                            _ => self.share.limits.min_uniform_buffer_offset_alignment,
+                        };
+                        if start % alignment != 0 {
+                            error!(
+                                "Buffer descriptor offset {} is not aligned to {}",
+                                start, alignment
+                            );
+                            continue;
+                        }
 
                         bindings.push(n::DescSetBindings::Buffer {
-                            ty: n::BindingTypes::UniformBuffers,
+                            ty: buffer_ty,
                             binding,
                             buffer: buffer.raw,
-                            offset,
+                            offset: start as _,
                             size,
                         });
-
-                        offset += size;
                     }
                     pso::Descriptor::CombinedImageSampler(view, _layout, sampler) => {
                         match view {
@@ -119,7 +119,7 @@ fn get_string(gl: &GlContainer, name: gl::types::GLenum) -> &'static str {
     }
 }
 
-fn get_usize(gl: &GlContainer, name: gl::types::GLenum) -> Result<usize, Error> {
+pub(crate) fn get_usize(gl: &GlContainer, name: gl::types::GLenum) -> Result<usize, Error> {
     let mut value = 0 as gl::types::GLint;
     unsafe { gl.GetIntegerv(name, &mut value) };
 
@@ -157,7 +157,7 @@ impl PlatformName {
 /// Private capabilities that don't need to be exposed.
 /// The affect the implementation code paths but not the
 /// provided API surface.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct PrivateCaps {
     /// VAO support
     pub vertex_array: bool,
@@ -165,6 +165,20 @@ pub struct PrivateCaps {
     pub framebuffer: bool,
     /// FBO support to call `glFramebufferTexture`
     pub framebuffer_texture: bool,
+    /// Can invalidate FBO attachments with `glInvalidateFramebuffer`
+    pub framebuffer_invalidate: bool,
+    /// Can create FBOs without attachments with `glFramebufferParameteri`
+    pub framebuffer_no_attachments: bool,
+    /// Can create layered FBOs without attachments with `GL_FRAMEBUFFER_DEFAULT_LAYERS`
+    pub framebuffer_no_attachments_layers: bool,
+    /// Can copy between textures with `glCopyImageSubData`
+    pub copy_image: bool,
+    /// Can select the provoking vertex with `glProvokingVertex`
+    pub provoking_vertex: bool,
+    /// Can order shader storage writes with `glMemoryBarrier`
+    pub memory_barrier: bool,
+    /// Can bind shader storage buffers with `GL_SHADER_STORAGE_BUFFER`
+    pub storage_buffer: bool,
     /// Can bind a buffer to a different target than was
     /// used upon the buffer creation/initialization
     pub buffer_role_change: bool,
@@ -312,9 +326,9 @@ pub(crate) fn query_all(gl: &GlContainer) -> (Info, Features, LegacyFeatures, Li
         max_viewports: 1,
         min_buffer_copy_offset_alignment: 1,
         min_buffer_copy_pitch_alignment: 1,
-        min_texel_buffer_offset_alignment: 1,   // TODO
-        min_uniform_buffer_offset_alignment: 1, // TODO
-        min_storage_buffer_offset_alignment: 1, // TODO
+        min_texel_buffer_offset_alignment: 1, // TODO
+        min_uniform_buffer_offset_alignment: 1,
+        min_storage_buffer_offset_alignment: 1,
         ..Limits::default()
     };
 
@@ -325,6 +339,18 @@ pub(crate) fn query_all(gl: &GlContainer) -> (Info, Features, LegacyFeatures, Li
         // TODO: extension
         limits.max_viewports = get_usize(gl, gl::MAX_VIEWPORTS).unwrap_or(0);
     }
+    if info.is_supported(&[Core(3, 1), Es(3, 0), Ext("GL_ARB_uniform_buffer_object")]) {
+        limits.min_uniform_buffer_offset_alignment =
+            get_usize(gl, gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT).unwrap_or(1) as _;
+    }
+    if info.is_supported(&[
+        Core(4, 3),
+        Es(3, 1),
+        Ext("GL_ARB_shader_storage_buffer_object"),
+    ]) {
+        limits.min_storage_buffer_offset_alignment =
+            get_usize(gl, gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT).unwrap_or(1) as _;
+    }
 
     if false
         && info.is_supported(&[
@@ -369,6 +395,13 @@ pub(crate) fn query_all(gl: &GlContainer) -> (Info, Features, LegacyFeatures, Li
         // TODO: extension
         features |= Features::SAMPLER_MIP_LOD_BIAS;
     }
+    if info.is_supported(&[
+        Core(3, 3),
+        Ext("GL_ARB_blend_func_extended"),
+        Ext("GL_EXT_blend_func_extended"),
+    ]) {
+        features |= Features::DUAL_SRC_BLENDING;
+    }
 
     if info.is_supported(&[Core(4, 3), Es(3, 1)]) {
         // TODO: extension
@@ -438,6 +471,31 @@ pub(crate) fn query_all(gl: &GlContainer) -> (Info, Features, LegacyFeatures, Li
         framebuffer: info.is_supported(&[Core(3, 0), Es(2, 0), Ext("GL_ARB_framebuffer_object")])
             && gl.GenFramebuffers.is_loaded(),
         framebuffer_texture: info.is_supported(&[Core(3, 0)]), //TODO: double check
+        framebuffer_invalidate: info.is_supported(&[
+            Core(4, 3),
+            Es(3, 0),
+            Ext("GL_ARB_invalidate_subdata"),
+        ]) && gl.InvalidateFramebuffer.is_loaded(),
+        framebuffer_no_attachments: info.is_supported(&[
+            Core(4, 3),
+            Es(3, 1),
+            Ext("GL_ARB_framebuffer_no_attachments"),
+        ]) && gl.FramebufferParameteri.is_loaded(),
+        framebuffer_no_attachments_layers: info.is_supported(&[
+            Core(4, 3),
+            Es(3, 2),
+            Ext("GL_ARB_framebuffer_no_attachments"),
+        ]) && gl.FramebufferParameteri.is_loaded(),
+        copy_image: info.is_supported(&[Core(4, 3), Es(3, 2), Ext("GL_ARB_copy_image")])
+            && gl.CopyImageSubData.is_loaded(),
+        provoking_vertex: info.is_supported(&[Core(3, 2), Ext("GL_ARB_provoking_vertex")])
+            && gl.ProvokingVertex.is_loaded(),
+        memory_barrier: info.is_supported(&[Core(4, 3), Es(3, 1)]) && gl.MemoryBarrier.is_loaded(),
+        storage_buffer: info.is_supported(&[
+            Core(4, 3),
+            Es(3, 1),
+            Ext("GL_ARB_shader_storage_buffer_object"),
+        ]),
         buffer_role_change: !info.version.is_embedded,
         image_storage: info.is_supported(&[Core(4, 2), Ext("GL_ARB_texture_storage")]),
         buffer_storage: info.is_supported(&[Core(4, 4), Ext("GL_ARB_buffer_storage")]),
@@ -1,5 +1,10 @@
 //! OpenGL implementation of a device, striving to support OpenGL 2.0 with at
 //! least VAOs, but using newer extensions when available.
+//!
+//! Flat-shaded outputs take their value from the first vertex of a primitive,
+//! as in Vulkan and D3D, when `glProvokingVertex` is available (GL 3.2 or
+//! `GL_ARB_provoking_vertex`). OpenGL ES has no equivalent and keeps GL's
+//! default of using the last vertex.
 
 #![allow(missing_docs, missing_copy_implementations)]
 
@@ -382,6 +387,11 @@ impl hal::PhysicalDevice<Backend> for PhysicalDevice {
             gl.Enable(gl::PROGRAM_POINT_SIZE);
         }
 
+        // Flat shading uses the first vertex of a primitive, as in Vulkan and D3D.
+        if self.0.private_caps.provoking_vertex {
+            gl.ProvokingVertex(gl::FIRST_VERTEX_CONVENTION);
+        }
+
         // create main VAO and bind it
         let mut vao = 0;
         if self.0.private_caps.vertex_array {
@@ -45,6 +45,7 @@ impl Fence {
 pub enum BindingTypes {
     Images,
     UniformBuffers,
+    StorageBuffers,
 }
 
 #[derive(Clone, Debug)]
@@ -133,6 +134,7 @@ pub struct GraphicsPipeline {
     pub(crate) primitive: gl::types::GLenum,
     pub(crate) patch_size: Option<gl::types::GLint>,
     pub(crate) blend_targets: Vec<pso::ColorBlendDesc>,
+    pub(crate) stencil: pso::StencilTest,
     pub(crate) attributes: Vec<AttributeDesc>,
     pub(crate) vertex_buffers: Vec<Option<pso::VertexBufferDesc>>,
 }
@@ -187,7 +189,7 @@ pub(crate) enum DescSetBindings {
 
 #[derive(Clone, Debug)]
 pub struct DescriptorSet {
-    layout: DescriptorSetLayout,
+    pub(crate) layout: DescriptorSetLayout,
     pub(crate) bindings: Arc<Mutex<Vec<DescSetBindings>>>,
 }
 
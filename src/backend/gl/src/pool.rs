@@ -1,6 +1,7 @@
 use command::{self, Command, RawCommandBuffer};
 use hal::backend::FastHashMap;
 use hal::{self, pool};
+use info::PrivateCaps;
 use native as n;
 use Backend;
 
@@ -53,7 +54,9 @@ pub enum BufferMemory {
 
 pub struct RawCommandPool {
     pub(crate) fbo: Option<n::FrameBuffer>,
+    pub(crate) blit_fbo: Option<n::FrameBuffer>,
     pub(crate) limits: command::Limits,
+    pub(crate) private_caps: PrivateCaps,
     pub(crate) memory: Arc<Mutex<BufferMemory>>,
 }
 
@@ -80,7 +83,13 @@ impl pool::RawCommandPool<Backend> for RawCommandPool {
 
     fn allocate_one(&mut self, _level: hal::command::RawLevel) -> RawCommandBuffer {
         // TODO: Implement secondary buffers
-        RawCommandBuffer::new(self.fbo, self.limits, self.memory.clone())
+        RawCommandBuffer::new(
+            self.fbo,
+            self.blit_fbo,
+            self.limits,
+            self.private_caps,
+            self.memory.clone(),
+        )
     }
 
     unsafe fn free<I>(&mut self, buffers: I)
@@ -151,6 +151,49 @@ impl CommandQueue {
     }
     */
 
+    /// Copy between two images with `glCopyImageSubData`.
+    ///
+    /// Command buffers record blits instead when it's not available.
+    fn copy_image(
+        &self,
+        src: native::ImageKind,
+        dst: native::ImageKind,
+        r: &hal::command::ImageCopy,
+    ) {
+        fn name_target(kind: native::ImageKind) -> (gl::types::GLuint, gl::types::GLenum) {
+            match kind {
+                native::ImageKind::Surface(surface) => (surface, gl::RENDERBUFFER),
+                native::ImageKind::Texture(texture) => (texture, gl::TEXTURE_2D),
+            }
+        }
+
+        if !self.share.private_caps.copy_image {
+            error!("Copying images requires glCopyImageSubData");
+            return;
+        }
+        let (src_name, src_target) = name_target(src);
+        let (dst_name, dst_target) = name_target(dst);
+        unsafe {
+            self.share.context.CopyImageSubData(
+                src_name,
+                src_target,
+                r.src_subresource.level as _,
+                r.src_offset.x,
+                r.src_offset.y,
+                r.src_offset.z,
+                dst_name,
+                dst_target,
+                r.dst_subresource.level as _,
+                r.dst_offset.x,
+                r.dst_offset.y,
+                r.dst_offset.z,
+                r.extent.width as _,
+                r.extent.height as _,
+                r.extent.depth as _,
+            );
+        }
+    }
+
     fn bind_target(
         &mut self,
         point: gl::types::GLenum,
@@ -472,6 +515,24 @@ impl CommandQueue {
             com::Command::SetBlendColor(color) => {
                 state::set_blend_color(&self.share.context, color);
             }
+            com::Command::BlitFramebuffer {
+                src,
+                dst,
+                mask,
+                filter,
+            } => unsafe {
+                self.share.context.BlitFramebuffer(
+                    src[0], src[1], src[2], src[3], dst[0], dst[1], dst[2], dst[3], mask, filter,
+                );
+            },
+            com::Command::MemoryBarrier(bits) => {
+                if self.share.private_caps.memory_barrier {
+                    unsafe { self.share.context.MemoryBarrier(bits) };
+                }
+            }
+            com::Command::SetStencilState(ref stencil, refs, read_masks, write_masks) => {
+                state::bind_stencil(&self.share.context, stencil, refs, read_masks, write_masks);
+            }
             com::Command::ClearBufferColorF(draw_buffer, cv) => unsafe {
                 self.share
                     .context
@@ -506,6 +567,18 @@ impl CommandQueue {
                     .context
                     .DrawBuffers(draw_buffers.len() as _, draw_buffers.as_ptr());
             },
+            com::Command::InvalidateFramebuffer(point, attachments) => {
+                if self.share.private_caps.framebuffer_invalidate {
+                    let attachments = Self::get::<gl::types::GLenum>(data_buf, attachments);
+                    unsafe {
+                        self.share.context.InvalidateFramebuffer(
+                            point,
+                            attachments.len() as _,
+                            attachments.as_ptr(),
+                        )
+                    };
+                }
+            }
             com::Command::BindFrameBuffer(point, frame_buffer) => {
                 if self.share.private_caps.framebuffer {
                     let gl = &self.share.context;
@@ -631,11 +704,11 @@ impl CommandQueue {
             com::Command::CopySurfaceToBuffer(..) => {
                 unimplemented!() //TODO: use FBO
             }
-            com::Command::CopyImageToTexture(..) => {
-                unimplemented!() //TODO: use FBO
+            com::Command::CopyImageToTexture(src, dst, ref r) => {
+                self.copy_image(src, native::ImageKind::Texture(dst), r);
             }
-            com::Command::CopyImageToSurface(..) => {
-                unimplemented!() //TODO: use FBO
+            com::Command::CopyImageToSurface(src, dst, ref r) => {
+                self.copy_image(src, native::ImageKind::Surface(dst), r);
             }
             com::Command::BindBufferRange(target, index, buffer, offset, size) => unsafe {
                 let gl = &self.share.context;
@@ -719,9 +792,6 @@ impl CommandQueue {
                com::Command::SetDepthState(depth) => {
                    state::bind_depth(&self.share.context, &depth);
                },
-               com::Command::SetStencilState(stencil, refs, cull) => {
-                   state::bind_stencil(&self.share.context, &stencil, refs, cull);
-               },
                com::Command::SetBlendState(slot, color) => {
                    if self.share.capabilities.separate_blending_slots {
                        state::bind_blend_slot(&self.share.context, slot, color);
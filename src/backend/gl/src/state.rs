@@ -118,24 +118,31 @@ pub(crate) fn bind_stencil(
     gl: &GlContainer,
     stencil: &pso::StencilTest,
     (ref_front, ref_back): (pso::StencilValue, pso::StencilValue),
-    cull: Option<pso::Face>,
+    (read_front, read_back): (pso::StencilValue, pso::StencilValue),
+    (write_front, write_back): (pso::StencilValue, pso::StencilValue),
 ) {
     fn bind_side(
         gl: &GlContainer,
         face: gl::types::GLenum,
         side: &pso::StencilFace,
         ref_value: pso::StencilValue,
+        mask_read: pso::StencilValue,
+        mask_write: pso::StencilValue,
     ) {
         unsafe {
+            let rv = match side.reference {
+                pso::State::Static(v) => v,
+                pso::State::Dynamic => ref_value,
+            };
             let mr = match side.mask_read {
                 pso::State::Static(v) => v,
-                pso::State::Dynamic => !0,
+                pso::State::Dynamic => mask_read,
             };
             let mw = match side.mask_write {
                 pso::State::Static(v) => v,
-                pso::State::Dynamic => !0,
+                pso::State::Dynamic => mask_write,
             };
-            gl.StencilFuncSeparate(face, map_comparison(side.fun), ref_value as _, mr);
+            gl.StencilFuncSeparate(face, map_comparison(side.fun), rv as _, mr);
             gl.StencilMaskSeparate(face, mw);
             gl.StencilOpSeparate(
                 face,
@@ -151,14 +158,8 @@ pub(crate) fn bind_stencil(
             ref back,
         } => {
             unsafe { gl.Enable(gl::STENCIL_TEST) };
-            if let Some(cf) = cull {
-                if !cf.contains(pso::Face::FRONT) {
-                    bind_side(gl, gl::FRONT, front, ref_front);
-                }
-                if !cf.contains(pso::Face::BACK) {
-                    bind_side(gl, gl::BACK, back, ref_back);
-                }
-            }
+            bind_side(gl, gl::FRONT, front, ref_front, read_front, write_front);
+            bind_side(gl, gl::BACK, back, ref_back, read_back, write_back);
         }
         pso::StencilTest::Off => unsafe {
             gl.Disable(gl::STENCIL_TEST);
@@ -266,3 +267,24 @@ pub(crate) fn unlock_color_mask(gl: &GlContainer) {
 pub(crate) fn set_blend_color(gl: &GlContainer, color: pso::ColorValue) {
     unsafe { gl.BlendColor(color[0], color[1], color[2], color[3]) };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::map_factor;
+    use gl;
+    use hal::pso::Factor;
+
+    #[test]
+    fn test_map_dual_source_factors() {
+        assert_eq!(map_factor(Factor::Src1Color), gl::SRC1_COLOR);
+        assert_eq!(
+            map_factor(Factor::OneMinusSrc1Color),
+            gl::ONE_MINUS_SRC1_COLOR
+        );
+        assert_eq!(map_factor(Factor::Src1Alpha), gl::SRC1_ALPHA);
+        assert_eq!(
+            map_factor(Factor::OneMinusSrc1Alpha),
+            gl::ONE_MINUS_SRC1_ALPHA
+        );
+    }
+}
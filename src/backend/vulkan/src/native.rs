@@ -65,6 +65,16 @@ pub struct Framebuffer {
     pub(crate) raw: vk::Framebuffer,
 }
 
+impl Framebuffer {
+    /// Get the native Vulkan framebuffer.
+    ///
+    /// Required for FFI with external libraries. The handle stays owned by
+    /// this object and becomes invalid after `destroy_framebuffer`.
+    pub unsafe fn as_raw(&self) -> vk::Framebuffer {
+        self.raw
+    }
+}
+
 #[derive(Debug)]
 pub struct DescriptorSetLayout {
     pub(crate) raw: vk::DescriptorSetLayout,
@@ -7,8 +7,8 @@ use super::{
     Primary, RawCommandBuffer, RenderPassInlineEncoder, RenderPassSecondaryEncoder, Shot,
 };
 use queue::capability::{Graphics, GraphicsOrCompute, Supports};
-use Backend;
 use {buffer, image, pso, query};
+use {Backend, IndexCount, InstanceCount, VertexCount, VertexOffset};
 
 /// A universal clear color supporting integer formats
 /// as well as the standard floating-point.
@@ -163,6 +163,42 @@ pub struct ImageBlit {
     pub dst_bounds: Range<image::Offset>,
 }
 
+/// Layout of a single command in the buffer passed to `draw_indirect`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DrawIndirectCommand {
+    /// Number of vertices to draw.
+    pub vertex_count: VertexCount,
+    /// Number of instances to draw.
+    pub instance_count: InstanceCount,
+    /// Index of the first vertex to draw.
+    pub first_vertex: VertexCount,
+    /// Instance ID of the first instance to draw.
+    ///
+    /// Must be 0 unless `Features::DRAW_INDIRECT_FIRST_INSTANCE` is supported.
+    pub first_instance: InstanceCount,
+}
+
+/// Layout of a single command in the buffer passed to `draw_indexed_indirect`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DrawIndexedIndirectCommand {
+    /// Number of indices to draw.
+    pub index_count: IndexCount,
+    /// Number of instances to draw.
+    pub instance_count: InstanceCount,
+    /// Index of the first index to draw.
+    pub first_index: IndexCount,
+    /// Value added to each index before fetching the vertex.
+    pub vertex_offset: VertexOffset,
+    /// Instance ID of the first instance to draw.
+    ///
+    /// Must be 0 unless `Features::DRAW_INDIRECT_FIRST_INSTANCE` is supported.
+    pub first_instance: InstanceCount,
+}
+
 impl<B: Backend, C: Supports<Graphics>, S: Shot, L: Level> CommandBuffer<B, C, S, L> {
     /// Identical to the `RawCommandBuffer` method of the same name.
     pub unsafe fn clear_image<T>(
@@ -387,3 +423,34 @@ impl<B: Backend, C: Supports<GraphicsOrCompute>, S: Shot, L: Level> CommandBuffe
         self.raw.write_timestamp(stage, query)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DrawIndexedIndirectCommand, DrawIndirectCommand};
+    use std::mem;
+
+    fn offset<T, F>(base: &T, field: &F) -> usize {
+        field as *const F as usize - base as *const T as usize
+    }
+
+    #[test]
+    fn test_draw_indirect_command_layout() {
+        let cmd = DrawIndirectCommand::default();
+        assert_eq!(mem::size_of::<DrawIndirectCommand>(), 16);
+        assert_eq!(offset(&cmd, &cmd.vertex_count), 0);
+        assert_eq!(offset(&cmd, &cmd.instance_count), 4);
+        assert_eq!(offset(&cmd, &cmd.first_vertex), 8);
+        assert_eq!(offset(&cmd, &cmd.first_instance), 12);
+    }
+
+    #[test]
+    fn test_draw_indexed_indirect_command_layout() {
+        let cmd = DrawIndexedIndirectCommand::default();
+        assert_eq!(mem::size_of::<DrawIndexedIndirectCommand>(), 20);
+        assert_eq!(offset(&cmd, &cmd.index_count), 0);
+        assert_eq!(offset(&cmd, &cmd.instance_count), 4);
+        assert_eq!(offset(&cmd, &cmd.first_index), 8);
+        assert_eq!(offset(&cmd, &cmd.vertex_offset), 12);
+        assert_eq!(offset(&cmd, &cmd.first_instance), 16);
+    }
+}
@@ -450,7 +450,7 @@ pub trait RawCommandBuffer<B: Backend>: Any + Send + Sync {
     /// Each draw command in the buffer is a series of 4 `u32` values specifying,
     /// in order, the number of vertices to draw, the number of instances to draw,
     /// the index of the first vertex to draw, and the instance ID of the first
-    /// instance to draw.
+    /// instance to draw. See `DrawIndirectCommand` for the exact layout.
     unsafe fn draw_indirect(
         &mut self,
         buffer: &B::Buffer,
@@ -465,7 +465,8 @@ pub trait RawCommandBuffer<B: Backend>: Any + Send + Sync {
     /// Each draw command in the buffer is a series of 5 values specifying,
     /// in order, the number of indices, the number of instances, the first index,
     /// the vertex offset, and the first instance.  All are `u32`'s except
-    /// the vertex offset, which is an `i32`.
+    /// the vertex offset, which is an `i32`. See `DrawIndexedIndirectCommand`
+    /// for the exact layout.
     unsafe fn draw_indexed_indirect(
         &mut self,
         buffer: &B::Buffer,
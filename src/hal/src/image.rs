@@ -368,6 +368,12 @@ bitflags!(
         /// Support creation of `Cube` and `CubeArray` kinds of views.
         const KIND_CUBE      = 0x00000010;
         /// Support creation of `D2Array` kind of view.
+        ///
+        /// On a `D3` image this also requests `D2` and `D2Array` views of its depth
+        /// slices, which can then be used as color attachments to render into
+        /// individual slices of a volume texture. Support for that is backend
+        /// dependent (Vulkan needs `VK_KHR_maintenance1`, Metal has none), so check
+        /// `PhysicalDevice::image_format_properties` before creating such an image.
         const KIND_2D_ARRAY  = 0x00000020;
     }
 );
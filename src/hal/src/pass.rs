@@ -18,7 +18,7 @@ pub enum AttachmentLoadOp {
     DontCare,
 }
 
-///
+/// Specifies the operation which will be applied at the end of a subpass.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AttachmentStoreOp {
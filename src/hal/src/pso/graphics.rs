@@ -21,6 +21,72 @@ pub struct Rect {
     pub h: i16,
 }
 
+impl Rect {
+    /// Create a rect of the given size, centered within `area`.
+    ///
+    /// If the size exceeds `area`, the result extends past it equally on both sides.
+    /// The position is clamped to the `i16` range.
+    pub fn centered(w: i16, h: i16, area: Rect) -> Self {
+        let center = |base: i16, size: i16, inner: i16| {
+            clamp_i16(base as i32 + (size as i32 - inner as i32) / 2)
+        };
+        Rect {
+            x: center(area.x, area.w, w),
+            y: center(area.y, area.h, h),
+            w,
+            h,
+        }
+    }
+
+    /// Create a rect from coordinates normalized to `area`,
+    /// where `(0.0, 0.0)` is the origin of `area` and `(1.0, 1.0)` its far corner.
+    ///
+    /// Both edges are rounded to the nearest pixel separately, so rects that share
+    /// a normalized edge also share a pixel edge. The result is clamped to `area`
+    /// and to the `i16` range.
+    pub fn from_normalized(x: f32, y: f32, w: f32, h: f32, area: Rect) -> Self {
+        let edge = |base: i16, size: i16, value: f32| {
+            let offset = (value * size as f32).round().max(0.0).min(size as f32);
+            clamp_i16(base as i32 + offset as i32)
+        };
+        let (x0, x1) = (edge(area.x, area.w, x), edge(area.x, area.w, x + w));
+        let (y0, y1) = (edge(area.y, area.h, y), edge(area.y, area.h, y + h));
+        Rect {
+            x: x0,
+            y: y0,
+            w: (x1 - x0).max(0),
+            h: (y1 - y0).max(0),
+        }
+    }
+
+    /// Compute the overlapping area of two rects.
+    ///
+    /// Returns `None` if the rects don't overlap,
+    /// including when they only touch at an edge.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x as i32 + self.w as i32).min(other.x as i32 + other.w as i32);
+        let y1 = (self.y as i32 + self.h as i32).min(other.y as i32 + other.h as i32);
+        if x1 > x0 as i32 && y1 > y0 as i32 {
+            Some(Rect {
+                x: x0,
+                y: y0,
+                w: (x1 - x0 as i32) as i16,
+                h: (y1 - y0 as i32) as i16,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn clamp_i16(value: i32) -> i16 {
+    value
+        .max(i16::min_value() as i32)
+        .min(i16::max_value() as i32) as i16
+}
+
 /// A simple struct describing a rect with integer coordinates.
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -262,17 +328,22 @@ pub enum LogicOp {
     Set = 15,
 }
 
-///
+/// Mask of the samples written by a fragment, one bit per sample.
 pub type SampleMask = u64;
 
-///
+/// Multisampling state of a graphics pipeline.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Multisampling {
-    ///
+    /// Number of samples per pixel used during rasterization.
+    /// Must match the sample count of the subpass attachments.
     pub rasterization_samples: image::NumSamples,
-    ///
+    /// Enables sample shading: at least this fraction of the samples of each
+    /// pixel, in the `0.0 ..= 1.0` range, is shaded independently.
+    /// `Some(1.0)` forces shading every sample.
+    /// Requires `Features::SAMPLE_RATE_SHADING`.
     pub sample_shading: Option<f32>,
-    ///
+    /// Static coverage mask, ANDed with the coverage of each fragment.
+    /// Bit `i` controls sample `i`; `!0` leaves the coverage untouched.
     pub sample_mask: SampleMask,
     /// Toggles alpha-to-coverage multisampling, which can produce nicer edges
     /// when many partially-transparent polygons are overlapping.
@@ -281,3 +352,66 @@ pub struct Multisampling {
     ///
     pub alpha_to_one: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Rect;
+
+    fn rect(x: i16, y: i16, w: i16, h: i16) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    #[test]
+    fn test_rect_intersect() {
+        let a = rect(0, 0, 10, 10);
+        assert_eq!(a.intersect(&rect(5, 5, 10, 10)), Some(rect(5, 5, 5, 5)));
+        assert_eq!(a.intersect(&rect(2, 3, 4, 5)), Some(rect(2, 3, 4, 5)));
+        assert_eq!(a.intersect(&a), Some(a));
+        // touching edges and corners
+        assert_eq!(a.intersect(&rect(10, 0, 10, 10)), None);
+        assert_eq!(a.intersect(&rect(0, 10, 10, 10)), None);
+        assert_eq!(a.intersect(&rect(10, 10, 1, 1)), None);
+        // disjoint and zero-area
+        assert_eq!(a.intersect(&rect(20, 20, 5, 5)), None);
+        assert_eq!(a.intersect(&rect(5, 5, 0, 3)), None);
+        assert_eq!(a.intersect(&rect(-5, -5, 5, 20)), None);
+    }
+
+    #[test]
+    fn test_rect_centered() {
+        let area = rect(10, 20, 100, 50);
+        assert_eq!(Rect::centered(20, 10, area), rect(50, 40, 20, 10));
+        assert_eq!(Rect::centered(120, 50, area), rect(0, 20, 120, 50));
+        // far edge past i16::MAX
+        let far = rect(30000, 30000, 10000, 10000);
+        assert_eq!(
+            Rect::centered(100, 100, far),
+            rect(i16::max_value(), i16::max_value(), 100, 100)
+        );
+    }
+
+    #[test]
+    fn test_rect_from_normalized() {
+        let area = rect(0, 0, 100, 30);
+        assert_eq!(Rect::from_normalized(0.0, 0.0, 1.0, 1.0, area), area);
+        assert_eq!(
+            Rect::from_normalized(0.25, 0.5, 0.5, 0.5, area),
+            rect(25, 15, 50, 15)
+        );
+        // adjacent rects share an edge after rounding
+        let left = Rect::from_normalized(0.0, 0.0, 1.0 / 3.0, 1.0, area);
+        let right = Rect::from_normalized(1.0 / 3.0, 0.0, 2.0 / 3.0, 1.0, area);
+        assert_eq!(left.x + left.w, right.x);
+        // clamped to the area
+        assert_eq!(
+            Rect::from_normalized(-0.5, 0.5, 2.0, 1.0, area),
+            rect(0, 15, 100, 15)
+        );
+        // far edge past i16::MAX
+        let far = rect(30000, 0, 10000, 30);
+        assert_eq!(
+            Rect::from_normalized(0.0, 0.0, 0.5, 1.0, far),
+            rect(30000, 0, 2767, 30)
+        );
+    }
+}
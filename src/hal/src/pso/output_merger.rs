@@ -82,6 +82,32 @@ pub enum Factor {
     OneMinusSrc1Alpha = 18,
 }
 
+impl Factor {
+    /// Whether the factor references the second color output of the fragment shader.
+    ///
+    /// Such factors require `Features::DUAL_SRC_BLENDING`.
+    pub fn is_dual_source(self) -> bool {
+        match self {
+            Factor::Src1Color
+            | Factor::OneMinusSrc1Color
+            | Factor::Src1Alpha
+            | Factor::OneMinusSrc1Alpha => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the factor references the blend constant color.
+    pub fn uses_constant(self) -> bool {
+        match self {
+            Factor::ConstColor
+            | Factor::OneMinusConstColor
+            | Factor::ConstAlpha
+            | Factor::OneMinusConstAlpha => true,
+            _ => false,
+        }
+    }
+}
+
 /// Blending operations.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -123,6 +149,27 @@ impl BlendOp {
         src: Factor::One,
         dst: Factor::OneMinusSrcAlpha,
     };
+
+    /// Whether any of the operation factors reference the second color output
+    /// of the fragment shader.
+    pub fn is_dual_source(self) -> bool {
+        match self {
+            BlendOp::Add { src, dst }
+            | BlendOp::Sub { src, dst }
+            | BlendOp::RevSub { src, dst } => src.is_dual_source() || dst.is_dual_source(),
+            BlendOp::Min | BlendOp::Max => false,
+        }
+    }
+
+    /// Whether any of the operation factors reference the blend constant color.
+    pub fn uses_constant(self) -> bool {
+        match self {
+            BlendOp::Add { src, dst }
+            | BlendOp::Sub { src, dst }
+            | BlendOp::RevSub { src, dst } => src.uses_constant() || dst.uses_constant(),
+            BlendOp::Min | BlendOp::Max => false,
+        }
+    }
 }
 
 /// Specifies whether to use blending, and if so,
@@ -158,6 +205,14 @@ impl BlendState {
             dst: Factor::SrcAlpha,
         },
     };
+    /// Screen blending, the inverse of multiplying the inverted colors.
+    pub const SCREEN: Self = BlendState::On {
+        color: BlendOp::Add {
+            src: Factor::One,
+            dst: Factor::OneMinusSrcColor,
+        },
+        alpha: BlendOp::PREMULTIPLIED_ALPHA,
+    };
     /// Alpha blending.
     pub const ALPHA: Self = BlendState::On {
         color: BlendOp::ALPHA,
@@ -168,6 +223,26 @@ impl BlendState {
         color: BlendOp::PREMULTIPLIED_ALPHA,
         alpha: BlendOp::PREMULTIPLIED_ALPHA,
     };
+
+    /// Whether the blend state requires dual-source blending,
+    /// i.e. the fragment shader has to write a second color output
+    /// for this target (`index = 1`).
+    pub fn is_dual_source(self) -> bool {
+        match self {
+            BlendState::On { color, alpha } => color.is_dual_source() || alpha.is_dual_source(),
+            BlendState::Off => false,
+        }
+    }
+
+    /// Whether the blend state references the blend constant color,
+    /// which then has to be provided either by `BakedStates::blend_color`
+    /// or by `set_blend_constants`.
+    pub fn uses_constant(self) -> bool {
+        match self {
+            BlendState::On { color, alpha } => color.uses_constant() || alpha.uses_constant(),
+            BlendState::Off => false,
+        }
+    }
 }
 
 impl Default for BlendState {
@@ -332,3 +407,102 @@ bitflags!(
         const BACK = 0x2;
     }
 );
+
+#[cfg(test)]
+mod tests {
+    use super::{BlendOp, BlendState, Factor};
+
+    /// A single channel of a pixel along with its alpha.
+    #[derive(Clone, Copy)]
+    struct Pixel {
+        value: f32,
+        alpha: f32,
+    }
+
+    fn factor(factor: Factor, src: Pixel, dst: Pixel) -> f32 {
+        match factor {
+            Factor::Zero => 0.0,
+            Factor::One => 1.0,
+            Factor::SrcColor => src.value,
+            Factor::OneMinusSrcColor => 1.0 - src.value,
+            Factor::DstColor => dst.value,
+            Factor::OneMinusDstColor => 1.0 - dst.value,
+            Factor::SrcAlpha => src.alpha,
+            Factor::OneMinusSrcAlpha => 1.0 - src.alpha,
+            Factor::DstAlpha => dst.alpha,
+            Factor::OneMinusDstAlpha => 1.0 - dst.alpha,
+            other => panic!("Unexpected factor {:?}", other),
+        }
+    }
+
+    fn blend(op: BlendOp, src: Pixel, dst: Pixel) -> f32 {
+        match op {
+            BlendOp::Add { src: sf, dst: df } => {
+                factor(sf, src, dst) * src.value + factor(df, src, dst) * dst.value
+            }
+            other => panic!("Unexpected operation {:?}", other),
+        }
+    }
+
+    /// Returns the blended `(color, alpha)` of `src` over `dst`.
+    fn apply(state: BlendState, src: Pixel, dst: Pixel) -> (f32, f32) {
+        let alpha = |p: Pixel| Pixel {
+            value: p.alpha,
+            alpha: p.alpha,
+        };
+        match state {
+            BlendState::On {
+                color,
+                alpha: alpha_op,
+            } => (
+                blend(color, src, dst),
+                blend(alpha_op, alpha(src), alpha(dst)),
+            ),
+            BlendState::Off => (src.value, src.alpha),
+        }
+    }
+
+    const SRC: Pixel = Pixel {
+        value: 0.5,
+        alpha: 0.25,
+    };
+    const DST: Pixel = Pixel {
+        value: 0.75,
+        alpha: 0.5,
+    };
+
+    #[test]
+    fn test_blend_presets_porter_duff() {
+        let (s, sa, d, da) = (SRC.value, SRC.alpha, DST.value, DST.alpha);
+        let over_alpha = sa + da * (1.0 - sa);
+
+        assert_eq!(apply(BlendState::ADD, SRC, DST), (s + d, sa + da));
+        assert_eq!(apply(BlendState::MULTIPLY, SRC, DST), (s * d, sa * da));
+        assert_eq!(
+            apply(BlendState::SCREEN, SRC, DST),
+            (s + d - s * d, over_alpha)
+        );
+        assert_eq!(
+            apply(BlendState::ALPHA, SRC, DST),
+            (s * sa + d * (1.0 - sa), over_alpha)
+        );
+        assert_eq!(
+            apply(BlendState::PREMULTIPLIED_ALPHA, SRC, DST),
+            (s + d * (1.0 - sa), over_alpha)
+        );
+    }
+
+    #[test]
+    fn test_blend_presets_are_plain() {
+        for &state in &[
+            BlendState::ADD,
+            BlendState::MULTIPLY,
+            BlendState::SCREEN,
+            BlendState::ALPHA,
+            BlendState::PREMULTIPLIED_ALPHA,
+        ] {
+            assert!(!state.is_dual_source(), "{:?}", state);
+            assert!(!state.uses_constant(), "{:?}", state);
+        }
+    }
+}